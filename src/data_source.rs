@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use chrono::TimeZone;
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+
+use crate::Candle;
+
+/// A pluggable feed of live candle data: a historical snapshot fetched once,
+/// followed by a stream of ticks for the candle currently forming.
+///
+/// A tick whose `time` matches the most recently known candle replaces that
+/// in-progress candle; a tick with a new `time` means its interval has
+/// rolled over and starts a new candle. This mirrors how exchange kline
+/// feeds report an open candle being updated until it closes.
+pub trait KlineSource: Send + 'static {
+    fn snapshot(&self) -> BoxFuture<'static, Vec<Candle>>;
+    fn ticks(self: Box<Self>) -> BoxStream<'static, Candle>;
+}
+
+/// Polls a REST kline endpoint on a fixed interval and reports its most
+/// recent candle as a tick. Targets exchange-style kline endpoints (e.g.
+/// Binance's `/api/v3/klines`), which return a JSON array of rows shaped
+/// `[open_time_ms, open, high, low, close, volume, ...]`; any trailing
+/// columns (close time, quote volume, trade count, ...) are ignored.
+pub struct HttpKlineSource {
+    url: String,
+    poll_interval: Duration,
+}
+
+impl HttpKlineSource {
+    pub fn new(url: impl Into<String>, poll_interval: Duration) -> Self {
+        Self {
+            url: url.into(),
+            poll_interval,
+        }
+    }
+
+    async fn fetch(url: &str) -> Result<Vec<Candle>, reqwest::Error> {
+        let rows: Vec<Vec<serde_json::Value>> = reqwest::get(url).await?.json().await?;
+        Ok(rows.iter().filter_map(|row| parse_kline_row(row)).collect())
+    }
+}
+
+/// Parse a single kline row's leading `[open_time_ms, open, high, low,
+/// close, volume]` columns, ignoring any columns after that.
+fn parse_kline_row(row: &[serde_json::Value]) -> Option<Candle> {
+    let open_time_ms = row.first()?.as_i64()?;
+    let open = row.get(1)?.as_str()?.parse().ok()?;
+    let high = row.get(2)?.as_str()?.parse().ok()?;
+    let low = row.get(3)?.as_str()?.parse().ok()?;
+    let close = row.get(4)?.as_str()?.parse().ok()?;
+    let volume = row.get(5).and_then(|v| v.as_str()).and_then(|v| v.parse().ok());
+
+    Some(Candle {
+        open,
+        high,
+        low,
+        close,
+        volume,
+        time: chrono::Utc.timestamp_millis_opt(open_time_ms).single()?,
+    })
+}
+
+impl KlineSource for HttpKlineSource {
+    fn snapshot(&self) -> BoxFuture<'static, Vec<Candle>> {
+        let url = self.url.clone();
+        Box::pin(async move { Self::fetch(&url).await.unwrap_or_default() })
+    }
+
+    fn ticks(self: Box<Self>) -> BoxStream<'static, Candle> {
+        Box::pin(futures::stream::unfold(*self, |source| async move {
+            loop {
+                tokio::time::sleep(source.poll_interval).await;
+
+                // A transient error (timeout, 429, 5xx) or an empty response is
+                // routine for a public endpoint — wait and retry rather than
+                // ending the feed, which would freeze the chart forever.
+                if let Some(candle) = Self::fetch(&source.url).await.ok().and_then(|mut rows| rows.pop()) {
+                    return Some((candle, source));
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_real_12_column_binance_kline_row() {
+        let row: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+                1499040000000,
+                "0.01634790",
+                "0.80000000",
+                "0.01575800",
+                "0.01577100",
+                "148976.11427815",
+                1499644799999,
+                "2434.19055334",
+                308,
+                "1756.87402397",
+                "28.46694368",
+                "0"
+            ]"#,
+        )
+        .unwrap();
+
+        let candle = parse_kline_row(&row).expect("12-column row should parse");
+
+        assert_eq!(candle.open, 0.0163479);
+        assert_eq!(candle.high, 0.8);
+        assert_eq!(candle.low, 0.015758);
+        assert_eq!(candle.close, 0.015771);
+        assert_eq!(candle.volume, Some(148976.11427815));
+        assert_eq!(candle.time.timestamp_millis(), 1499040000000);
+    }
+}
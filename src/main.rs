@@ -1,7 +1,12 @@
-use chrono::{DateTime, Utc, TimeZone};
-use iced::widget::canvas::{self, Canvas, Cursor, Frame, Path, Stroke, Text, Program, event, Event, Geometry};
-use iced::{Color, Rectangle, Theme, Element, Length, Settings, Sandbox};
-use rand::Rng;
+mod data_source;
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use data_source::{HttpKlineSource, KlineSource};
+use futures::StreamExt;
+use iced::widget::canvas::{self, Canvas, Cursor, Frame, LineDash, Path, Stroke, Text, Program, event, Event, Geometry};
+use iced::{Application, Color, Command, Point, Rectangle, Theme, Element, Length, Settings, Subscription};
 
 // Candle data structure
 #[derive(Debug, Clone)]
@@ -14,20 +19,156 @@ pub struct Candle {
     pub time: DateTime<Utc>,
 }
 
+/// The visible window into the candle series, expressed in the same index/price
+/// space the candles live in rather than as opaque zoom multipliers. Panning
+/// translates `first_index`/`price_center`; zooming shrinks or grows
+/// `visible_count`/`price_span` around the cursor's data coordinates.
+#[derive(Debug, Clone, Copy)]
+pub struct ViewRect {
+    pub first_index: f64,
+    pub visible_count: f64,
+    pub price_center: f64,
+    pub price_span: f64,
+}
+
+impl ViewRect {
+    /// A view that fits every candle in `candles`, matching the chart's
+    /// original always-show-everything behavior.
+    fn fit(candles: &[Candle]) -> Self {
+        let (min_price, max_price) = candles.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), c| (f64::min(min, c.low), f64::max(max, c.high)),
+        );
+
+        Self {
+            first_index: 0.0,
+            visible_count: candles.len() as f64,
+            price_center: (max_price + min_price) / 2.0,
+            price_span: (max_price - min_price).max(f64::EPSILON),
+        }
+    }
+}
+
+/// A moving-average overlay computed from the candle close prices and drawn
+/// as a polyline over the chart.
+#[derive(Debug, Clone, Copy)]
+pub enum Indicator {
+    Sma { period: usize, color: Color },
+    Ema { period: usize, color: Color },
+}
+
+impl Indicator {
+    fn color(&self) -> Color {
+        match *self {
+            Indicator::Sma { color, .. } => color,
+            Indicator::Ema { color, .. } => color,
+        }
+    }
+
+    fn period(&self) -> usize {
+        match *self {
+            Indicator::Sma { period, .. } => period,
+            Indicator::Ema { period, .. } => period,
+        }
+    }
+
+    /// Per-candle values aligned with `closes`; `None` until the window has
+    /// enough history behind it to produce a value.
+    fn values(&self, closes: &[f64]) -> Vec<Option<f64>> {
+        match *self {
+            Indicator::Sma { period, .. } => sma(closes, period),
+            Indicator::Ema { period, .. } => ema(closes, period),
+        }
+    }
+}
+
+/// Simple moving average: the mean of the previous `period` closes, emitting
+/// `None` until the window fills.
+fn sma(closes: &[f64], period: usize) -> Vec<Option<f64>> {
+    if period == 0 {
+        return vec![None; closes.len()];
+    }
+
+    (0..closes.len())
+        .map(|i| {
+            if i + 1 < period {
+                None
+            } else {
+                let window = &closes[i + 1 - period..=i];
+                Some(window.iter().sum::<f64>() / period as f64)
+            }
+        })
+        .collect()
+}
+
+/// Exponential moving average, seeded with the SMA of the first `period`
+/// closes and then following the standard recurrence.
+fn ema(closes: &[f64], period: usize) -> Vec<Option<f64>> {
+    if period == 0 || closes.len() < period {
+        return vec![None; closes.len()];
+    }
+
+    let k = 2.0 / (period as f64 + 1.0);
+    let mut values = vec![None; closes.len()];
+    values[period - 1] = Some(closes[..period].iter().sum::<f64>() / period as f64);
+
+    for i in period..closes.len() {
+        let prev = values[i - 1].expect("previous EMA value is always seeded");
+        values[i] = Some(closes[i] * k + prev * (1.0 - k));
+    }
+
+    values
+}
+
+/// Colors and proportions used to draw a `CandleChart`. Defaults to the
+/// theme's extended palette (see `ChartStyle::from_theme`) so the widget
+/// matches its host application out of the box; pass a custom one via
+/// `CandleChart::with_style` to override individual colors.
+#[derive(Debug, Clone, Copy)]
+pub struct ChartStyle {
+    pub bullish_body: Color,
+    pub bearish_body: Color,
+    pub wick: Color,
+    pub background: Color,
+    pub grid: Color,
+    pub text: Color,
+    pub candle_width_ratio: f32,
+}
+
+impl ChartStyle {
+    /// Derive a style from the application's active theme so the chart's
+    /// colors track a light or dark palette automatically.
+    fn from_theme(theme: &Theme) -> Self {
+        let palette = theme.extended_palette();
+
+        Self {
+            bullish_body: palette.success.base.color,
+            bearish_body: palette.danger.base.color,
+            wick: palette.background.base.text,
+            background: palette.background.base.color,
+            grid: palette.background.weak.color,
+            text: palette.background.base.text,
+            candle_width_ratio: 0.7,
+        }
+    }
+}
+
 // State of the chart, must implement Default
 #[derive(Debug, Clone)]
 pub struct CandleChartState {
-    middle_pressed: bool,
-    price_scale: f64,
-    time_scale: f64,
+    left_pressed: bool,
+    last_cursor_position: Option<Point>,
+    hover_position: Option<Point>,
+    view: Option<ViewRect>,
 }
 
 impl Default for CandleChartState {
     fn default() -> Self {
         Self {
-            middle_pressed: false,
-            price_scale: 1.0,
-            time_scale: 1.0,
+            left_pressed: false,
+            last_cursor_position: None,
+            hover_position: None,
+            view: None,
         }
     }
 }
@@ -36,12 +177,149 @@ impl Default for CandleChartState {
 #[derive(Debug, Clone)]
 pub struct CandleChart {
     pub candles: Vec<Candle>,
+    volume_pane_fraction: Option<f32>,
+    style: Option<ChartStyle>,
+    indicators: Vec<Indicator>,
 }
 
 impl CandleChart {
     pub fn new(candles: Vec<Candle>) -> Self {
-        Self { candles }
+        Self {
+            candles,
+            volume_pane_fraction: None,
+            style: None,
+            indicators: Vec::new(),
+        }
+    }
+
+    /// Overlay SMA/EMA lines computed from the candle close prices.
+    pub fn with_indicators(mut self, indicators: Vec<Indicator>) -> Self {
+        self.indicators = indicators;
+        self
+    }
+
+    /// Reserve the bottom `fraction` of the chart for a volume histogram,
+    /// scaled to the max volume currently on screen. Omit this call to keep
+    /// the original full-height price-only chart.
+    pub fn with_volume_pane(mut self, fraction: f32) -> Self {
+        self.volume_pane_fraction = Some(fraction.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Override the theme-derived colors and candle width. Omit this call to
+    /// have the chart match the active `Theme` automatically.
+    pub fn with_style(mut self, style: ChartStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Resolve the state's view, falling back to one that fits every candle
+    /// the first time the chart is drawn or interacted with.
+    fn effective_view(&self, view: Option<ViewRect>) -> ViewRect {
+        view.unwrap_or_else(|| ViewRect::fit(&self.candles))
+    }
+
+    fn candle_spacing(&self, bounds: Rectangle, view: ViewRect) -> f64 {
+        bounds.width as f64 / view.visible_count.max(1.0)
+    }
+
+    /// Split `bounds.height` into the price area and, if a volume pane is
+    /// enabled, the shorter volume area below it.
+    fn price_pane_height(&self, height: f64) -> f64 {
+        match self.volume_pane_fraction {
+            Some(fraction) => height * (1.0 - fraction as f64),
+            None => height,
+        }
     }
+
+    /// The slice of `candles` whose x-range can land inside `[0, width]`,
+    /// padded by one candle on each side so partially-visible edges still
+    /// draw. Folding/drawing over this instead of the whole series keeps the
+    /// widget responsive once there are tens of thousands of candles.
+    fn visible_range(&self, view: ViewRect) -> std::ops::Range<usize> {
+        let len = self.candles.len();
+        let start = (view.first_index.floor() - 1.0).max(0.0) as usize;
+        let end = ((view.first_index + view.visible_count).ceil() + 1.0).max(0.0) as usize;
+        start.min(len)..end.min(len)
+    }
+
+    /// The visible candles to draw, merging adjacent ones into aggregated
+    /// OHLCV "super-candles" once `candle_spacing` would otherwise put them
+    /// under ~2px, so the widget stays legible fully zoomed out. Each entry
+    /// is `(x_center, width, candle)`.
+    fn visible_series(
+        &self,
+        view: ViewRect,
+        range: std::ops::Range<usize>,
+        candle_spacing: f64,
+    ) -> Vec<(f64, f64, Candle)> {
+        let visible = &self.candles[range.clone()];
+
+        let bucket_size = if candle_spacing < 2.0 {
+            (2.0 / candle_spacing).ceil().max(1.0) as usize
+        } else {
+            1
+        };
+
+        if bucket_size <= 1 {
+            return visible
+                .iter()
+                .enumerate()
+                .map(|(offset, candle)| {
+                    let index = range.start + offset;
+                    let x_center = (index as f64 - view.first_index) * candle_spacing + candle_spacing / 2.0;
+                    (x_center, candle_spacing, candle.clone())
+                })
+                .collect();
+        }
+
+        // Anchor buckets to absolute multiples of `bucket_size` rather than to
+        // `range.start`, so aggregation boundaries stay fixed as the view pans
+        // instead of sliding (and the super-candles shimmering) every frame.
+        let first_bucket = range.start / bucket_size;
+        let last_bucket = (range.end.saturating_sub(1)) / bucket_size;
+
+        (first_bucket..=last_bucket)
+            .filter_map(|bucket| {
+                let bucket_start = bucket * bucket_size;
+                let bucket_end = (bucket_start + bucket_size).min(self.candles.len());
+                let chunk = self.candles.get(bucket_start..bucket_end)?;
+                if chunk.is_empty() {
+                    return None;
+                }
+
+                let group_width = candle_spacing * chunk.len() as f64;
+                let x_center = (bucket_start as f64 - view.first_index) * candle_spacing + group_width / 2.0;
+
+                let volumes: Vec<f64> = chunk.iter().filter_map(|c| c.volume).collect();
+                let aggregated = Candle {
+                    open: chunk.first().unwrap().open,
+                    close: chunk.last().unwrap().close,
+                    high: chunk.iter().fold(f64::NEG_INFINITY, |m, c| f64::max(m, c.high)),
+                    low: chunk.iter().fold(f64::INFINITY, |m, c| f64::min(m, c.low)),
+                    volume: (!volumes.is_empty()).then(|| volumes.iter().sum()),
+                    time: chunk.first().unwrap().time,
+                };
+
+                Some((x_center, group_width, aggregated))
+            })
+            .collect()
+    }
+}
+
+/// The rendered `(x_center, width, candle)` entry under `x` — the bar that
+/// was actually drawn there, aggregated super-candle or not — so a tooltip
+/// built from it always matches what's on screen. Falls back to the entry
+/// whose center is nearest `x` if none of them actually contain it.
+fn series_entry_at(series: &[(f64, f64, Candle)], x: f64) -> Option<&(f64, f64, Candle)> {
+    series
+        .iter()
+        .find(|(center, width, _)| x >= center - width / 2.0 && x < center + width / 2.0)
+        .or_else(|| {
+            series
+                .iter()
+                .min_by(|(a, ..), (b, ..)| (a - x).abs().partial_cmp(&(b - x).abs()).unwrap())
+        })
 }
 
 // Implement Program for CandleChart
@@ -61,47 +339,45 @@ impl<Message> Program<Message, Theme> for CandleChart {
             return vec![frame.into_geometry()];
         }
 
-        let (min_price, max_price) = self.candles.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), c| {
-            (f64::min(min, c.low), f64::max(max, c.high))
-        });
+        let view = self.effective_view(state.view);
+        let style = self.style.unwrap_or_else(|| ChartStyle::from_theme(theme));
 
         let height = bounds.height as f64;
         let width = bounds.width as f64;
+        let price_pane_height = self.price_pane_height(height);
 
-        let candle_count = self.candles.len() as f64;
-        let scaled_candle_count = candle_count * state.time_scale;
-        let candle_spacing = width / scaled_candle_count.max(1.0);
-        let candle_width = candle_spacing * 0.7;
+        let candle_spacing = self.candle_spacing(bounds, view);
 
-        let price_range = (max_price - min_price) / state.price_scale;
-        let mid_price = (max_price + min_price) / 2.0;
-        let scaled_min_price = mid_price - price_range / 2.0;
-        let scaled_max_price = mid_price + price_range / 2.0;
+        let scaled_min_price = view.price_center - view.price_span / 2.0;
+        let scaled_max_price = view.price_center + view.price_span / 2.0;
 
         let price_to_y = |p: f64| {
             let norm = (p - scaled_min_price) / (scaled_max_price - scaled_min_price + f64::EPSILON);
-            height - norm * height
+            price_pane_height - norm * price_pane_height
         };
 
         // Draw background
         frame.fill_rectangle(
             iced::Point::new(0.0, 0.0),
             iced::Size::new(bounds.width, bounds.height),
-            Color::from_rgb8(240, 240, 240),
+            style.background,
         );
 
-        // Draw candles
-        for (i, candle) in self.candles.iter().enumerate() {
-            let x_center = i as f64 * candle_spacing + candle_spacing / 2.0;
+        let visible_range = self.visible_range(view);
+        let visible_series = self.visible_series(view, visible_range.clone(), candle_spacing);
+
+        // Draw candles, merging into super-candles once zoomed out past ~2px/candle
+        for (x_center, group_width, candle) in &visible_series {
+            let candle_width = group_width * style.candle_width_ratio as f64;
 
             let high_y = price_to_y(candle.high);
             let low_y = price_to_y(candle.low);
 
             let stick_path = Path::line(
-                iced::Point::new(x_center as f32, high_y as f32),
-                iced::Point::new(x_center as f32, low_y as f32),
+                iced::Point::new(*x_center as f32, high_y as f32),
+                iced::Point::new(*x_center as f32, low_y as f32),
             );
-            frame.stroke(&stick_path, Stroke::default().with_color(Color::BLACK).with_width(1.0));
+            frame.stroke(&stick_path, Stroke::default().with_color(style.wick).with_width(1.0));
 
             let open_y = price_to_y(candle.open);
             let close_y = price_to_y(candle.close);
@@ -113,9 +389,9 @@ impl<Message> Program<Message, Theme> for CandleChart {
             };
 
             let body_color = if candle.close > candle.open {
-                Color::from_rgb(0.0, 0.7, 0.0) // green
+                style.bullish_body
             } else {
-                Color::from_rgb(0.7, 0.0, 0.0) // red
+                style.bearish_body
             };
 
             frame.fill_rectangle(
@@ -125,6 +401,92 @@ impl<Message> Program<Message, Theme> for CandleChart {
             );
         }
 
+        // Indicator overlays. An SMA only needs its period of lookback, so with
+        // SMAs alone just the visible window plus that lookback is collected
+        // into `closes`. An EMA depends on every earlier close (it is seeded at
+        // the start of the series), so any EMA makes `closes` start at index 0.
+        if !self.indicators.is_empty() {
+            let max_period = self.indicators.iter().map(Indicator::period).max().unwrap_or(0);
+            let has_ema = self.indicators.iter().any(|i| matches!(i, Indicator::Ema { .. }));
+            let lookback_start = if has_ema {
+                0
+            } else {
+                visible_range.start.saturating_sub(max_period)
+            };
+            let closes: Vec<f64> = self.candles[lookback_start..visible_range.end]
+                .iter()
+                .map(|c| c.close)
+                .collect();
+
+            for indicator in &self.indicators {
+                let values = indicator.values(&closes);
+
+                let path = Path::new(|builder| {
+                    let mut started = false;
+                    for i in visible_range.clone() {
+                        let Some(value) = values[i - lookback_start] else {
+                            continue;
+                        };
+
+                        let x_center = (i as f64 - view.first_index) * candle_spacing + candle_spacing / 2.0;
+                        let point = iced::Point::new(x_center as f32, price_to_y(value) as f32);
+
+                        if started {
+                            builder.line_to(point);
+                        } else {
+                            builder.move_to(point);
+                            started = true;
+                        }
+                    }
+                });
+
+                frame.stroke(&path, Stroke::default().with_color(indicator.color()).with_width(2.0));
+            }
+        }
+
+        // Volume sub-pane
+        if let Some(fraction) = self.volume_pane_fraction {
+            let volume_top = price_pane_height;
+            let volume_height = height * fraction as f64;
+
+            let max_volume = visible_series
+                .iter()
+                .filter_map(|(_, _, c)| c.volume)
+                .fold(0.0_f64, f64::max);
+
+            if max_volume > 0.0 {
+                for (x_center, group_width, candle) in &visible_series {
+                    let Some(volume) = candle.volume else {
+                        continue;
+                    };
+
+                    let candle_width = group_width * style.candle_width_ratio as f64;
+                    let bar_height = (volume / max_volume) * volume_height;
+
+                    let bar_color = if candle.close > candle.open {
+                        style.bullish_body
+                    } else {
+                        style.bearish_body
+                    };
+
+                    frame.fill_rectangle(
+                        iced::Point::new(
+                            (x_center - candle_width / 2.0) as f32,
+                            (volume_top + volume_height - bar_height) as f32,
+                        ),
+                        iced::Size::new(candle_width as f32, bar_height as f32),
+                        bar_color,
+                    );
+                }
+            }
+
+            let separator = Path::line(
+                iced::Point::new(0.0, volume_top as f32),
+                iced::Point::new(width as f32, volume_top as f32),
+            );
+            frame.stroke(&separator, Stroke::default().with_color(style.grid).with_width(1.0));
+        }
+
         // Vertical price labels
         let num_price_labels = 5;
         for j in 0..=num_price_labels {
@@ -133,7 +495,7 @@ impl<Message> Program<Message, Theme> for CandleChart {
             let mut text = Text {
                 content: format!("{:.2}", label_price),
                 position: iced::Point::new(5.0, y_pos as f32),
-                color: Color::BLACK,
+                color: style.text,
                 size: 14.0,
                 ..Text::default()
             };
@@ -145,21 +507,22 @@ impl<Message> Program<Message, Theme> for CandleChart {
                 iced::Point::new(0.0, y_pos as f32),
                 iced::Point::new(width as f32, y_pos as f32),
             );
-            frame.stroke(&grid_line, Stroke::default().with_color(Color::from_rgb8(200,200,200)).with_width(1.0));
+            frame.stroke(&grid_line, Stroke::default().with_color(style.grid).with_width(1.0));
         }
 
         // Horizontal time labels
         let num_time_labels = 5;
+        let time_label_y = price_pane_height - 20.0;
         if !self.candles.is_empty() {
             for k in 0..=num_time_labels {
                 let index = ((k as f64 / num_time_labels as f64) * (self.candles.len() as f64 - 1.0)) as usize;
                 if let Some(candle) = self.candles.get(index) {
-                    let x_center = index as f64 * candle_spacing + candle_spacing / 2.0;
+                    let x_center = (index as f64 - view.first_index) * candle_spacing + candle_spacing / 2.0;
                     let time_str = candle.time.format("%Y-%m-%d %H:%M").to_string();
                     let mut text = Text {
                         content: time_str,
-                        position: iced::Point::new(x_center as f32, (height - 20.0) as f32),
-                        color: Color::BLACK,
+                        position: iced::Point::new(x_center as f32, time_label_y as f32),
+                        color: style.text,
                         size: 14.0,
                         ..Text::default()
                     };
@@ -171,7 +534,80 @@ impl<Message> Program<Message, Theme> for CandleChart {
                         iced::Point::new(x_center as f32, 0.0),
                         iced::Point::new(x_center as f32, height as f32),
                     );
-                    frame.stroke(&grid_line, Stroke::default().with_color(Color::from_rgb8(220,220,220)).with_width(1.0));
+                    frame.stroke(&grid_line, Stroke::default().with_color(style.grid).with_width(1.0));
+                }
+            }
+        }
+
+        // Crosshair and OHLC tooltip, snapped to the bar actually drawn under the
+        // cursor — an aggregated super-candle when zoomed out past ~2px/candle
+        if let Some(hover) = state.hover_position {
+            if let Some((x_center, _, candle)) = series_entry_at(&visible_series, hover.x as f64) {
+                let x_center = *x_center;
+                let hovered_price = scaled_max_price
+                    - (hover.y as f64 / price_pane_height) * (scaled_max_price - scaled_min_price);
+
+                let dashed = Stroke {
+                    line_dash: LineDash {
+                        segments: &[4.0, 4.0],
+                        offset: 0,
+                    },
+                    ..Stroke::default().with_color(style.text).with_width(1.0)
+                };
+
+                let vertical = Path::line(
+                    iced::Point::new(x_center as f32, 0.0),
+                    iced::Point::new(x_center as f32, height as f32),
+                );
+                frame.stroke(&vertical, dashed);
+
+                let horizontal = Path::line(
+                    iced::Point::new(0.0, hover.y),
+                    iced::Point::new(width as f32, hover.y),
+                );
+                frame.stroke(&horizontal, dashed);
+
+                // Price label on the right axis
+                frame.fill_text(Text {
+                    content: format!("{:.2}", hovered_price),
+                    position: iced::Point::new(width as f32 - 55.0, hover.y - 7.0),
+                    color: style.text,
+                    size: 14.0,
+                    ..Text::default()
+                });
+
+                // Boxed OHLC(V) tooltip near the cursor
+                let mut lines = vec![
+                    candle.time.format("%Y-%m-%d %H:%M").to_string(),
+                    format!("O {:.2}  H {:.2}", candle.open, candle.high),
+                    format!("L {:.2}  C {:.2}", candle.low, candle.close),
+                ];
+                if let Some(volume) = candle.volume {
+                    lines.push(format!("V {:.0}", volume));
+                }
+
+                let tooltip_width = 150.0;
+                let line_height = 16.0;
+                let tooltip_height = line_height * lines.len() as f32 + 8.0;
+                let tooltip_origin = iced::Point::new(
+                    (hover.x + 12.0).min(width as f32 - tooltip_width),
+                    (hover.y + 12.0).min(height as f32 - tooltip_height),
+                );
+
+                frame.fill_rectangle(
+                    tooltip_origin,
+                    iced::Size::new(tooltip_width, tooltip_height),
+                    style.grid,
+                );
+
+                for (i, line) in lines.into_iter().enumerate() {
+                    frame.fill_text(Text {
+                        content: line,
+                        position: iced::Point::new(tooltip_origin.x + 6.0, tooltip_origin.y + 4.0 + i as f32 * line_height),
+                        color: style.text,
+                        size: 14.0,
+                        ..Text::default()
+                    });
                 }
             }
         }
@@ -186,34 +622,78 @@ impl<Message> Program<Message, Theme> for CandleChart {
         bounds: Rectangle,
         cursor: Cursor,
     ) -> (event::Status, Option<Message>) {
+        if self.candles.is_empty() {
+            return (event::Status::Ignored, None);
+        }
+
+        let mut view = self.effective_view(state.view);
+
         match event {
             Event::Mouse(mouse_event) => {
                 match mouse_event {
-                    iced::mouse::Event::ButtonPressed(iced::mouse::Button::Middle) => {
-                        state.middle_pressed = true;
+                    iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left) => {
+                        state.left_pressed = true;
+                        state.last_cursor_position = cursor.position();
                         (event::Status::Captured, None)
                     }
-                    iced::mouse::Event::ButtonReleased(iced::mouse::Button::Middle) => {
-                        state.middle_pressed = false;
+                    iced::mouse::Event::ButtonReleased(iced::mouse::Button::Left) => {
+                        state.left_pressed = false;
+                        state.last_cursor_position = None;
+                        (event::Status::Captured, None)
+                    }
+                    iced::mouse::Event::CursorMoved { .. } => {
+                        state.hover_position = cursor.position_in(bounds);
+
+                        if state.left_pressed {
+                            if let (Some(current), Some(last)) = (cursor.position(), state.last_cursor_position) {
+                                let dx = current.x as f64 - last.x as f64;
+                                let dy = current.y as f64 - last.y as f64;
+
+                                let candle_spacing = self.candle_spacing(bounds, view);
+                                let price_pane_height = self.price_pane_height(bounds.height as f64);
+                                view.first_index -= dx / candle_spacing;
+                                view.price_center += (dy / price_pane_height) * view.price_span;
+
+                                state.view = Some(view);
+                                state.last_cursor_position = Some(current);
+                                return (event::Status::Captured, None);
+                            }
+                        }
+                        (event::Status::Ignored, None)
+                    }
+                    iced::mouse::Event::CursorLeft => {
+                        state.hover_position = None;
                         (event::Status::Captured, None)
                     }
                     iced::mouse::Event::WheelScrolled { delta } => {
-                        if state.middle_pressed {
+                        if let Some(position) = cursor.position_in(bounds) {
                             let scroll_amount = match delta {
                                 iced::mouse::ScrollDelta::Lines { y, .. } => y,
                                 iced::mouse::ScrollDelta::Pixels { y, .. } => y / 50.0,
                             };
 
-                            let zoom_factor = 1.0 + (scroll_amount as f64 * 0.1);
-                            state.price_scale *= zoom_factor;
-                            state.time_scale *= zoom_factor;
+                            let mut zoom_factor = 1.0 - (scroll_amount as f64 * 0.1);
+                            zoom_factor = zoom_factor.clamp(0.1, 10.0);
+
+                            let candle_spacing = self.candle_spacing(bounds, view);
+                            let price_pane_height = self.price_pane_height(bounds.height as f64);
+                            let cursor_index = view.first_index + position.x as f64 / candle_spacing;
 
-                            // Clamp scales
-                            if state.price_scale < 0.1 { state.price_scale = 0.1; }
-                            if state.time_scale < 0.1 { state.time_scale = 0.1; }
-                            if state.price_scale > 10.0 { state.price_scale = 10.0; }
-                            if state.time_scale > 10.0 { state.time_scale = 10.0; }
+                            let scaled_min_price = view.price_center - view.price_span / 2.0;
+                            let cursor_price = scaled_min_price
+                                + (1.0 - position.y as f64 / price_pane_height) * view.price_span;
 
+                            let new_visible_count = (view.visible_count * zoom_factor).max(2.0);
+                            let new_price_span = (view.price_span * zoom_factor).max(f64::EPSILON);
+
+                            view.first_index = cursor_index - (position.x as f64 / bounds.width as f64) * new_visible_count;
+                            view.price_center = cursor_price
+                                - (1.0 - position.y as f64 / price_pane_height) * new_price_span
+                                + new_price_span / 2.0;
+                            view.visible_count = new_visible_count;
+                            view.price_span = new_price_span;
+
+                            state.view = Some(view);
                             (event::Status::Captured, None)
                         } else {
                             (event::Status::Ignored, None)
@@ -232,43 +712,37 @@ pub struct CandleChartApp {
 }
 
 #[derive(Debug, Clone)]
-pub enum Message {}
+pub enum Message {
+    /// The historical klines loaded when the data source's subscription starts.
+    SnapshotLoaded(Vec<Candle>),
+    /// A new or in-progress candle reported by the data source.
+    CandleUpdate(Candle),
+}
 
-impl Sandbox for CandleChartApp {
+impl Application for CandleChartApp {
+    type Executor = iced::executor::Default;
     type Message = Message;
+    type Theme = Theme;
+    type Flags = ();
 
-    fn new() -> Self {
-        let start = Utc.with_ymd_and_hms(2022, 10, 1, 0, 0, 0).unwrap();
-        let mut rng = rand::thread_rng();
-        let mut candles = Vec::new();
-
-        let mut last_close = 100.0;
-        for i in 0..24 {
-            let time = start + chrono::Duration::hours(i);
-            let open = last_close;
-            let high = open + (rng.gen::<f64>() * 5.0);
-            let low = open - (rng.gen::<f64>() * 5.0);
-            let close = low + (rng.gen::<f64>() * (high - low));
-            last_close = close;
-            candles.push(Candle {
-                open,
-                high,
-                low,
-                close,
-                volume: Some((rng.gen::<f64>() * 1000.0).abs()),
-                time,
-            });
-        }
-
-        CandleChartApp { candles }
+    fn new(_flags: ()) -> (Self, Command<Message>) {
+        (CandleChartApp { candles: Vec::new() }, Command::none())
     }
 
     fn title(&self) -> String {
         String::from("Candle Chart Demo")
     }
 
-    fn update(&mut self, _message: Message) {
-        // no messages
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::SnapshotLoaded(candles) => self.candles = candles,
+            Message::CandleUpdate(candle) => match self.candles.last_mut() {
+                Some(last) if last.time == candle.time => *last = candle,
+                _ => self.candles.push(candle),
+            },
+        }
+
+        Command::none()
     }
 
     fn view(&self) -> Element<Message> {
@@ -277,8 +751,156 @@ impl Sandbox for CandleChartApp {
             .height(Length::Fill)
             .into()
     }
+
+    fn subscription(&self) -> Subscription<Message> {
+        iced::subscription::channel(
+            std::any::TypeId::of::<HttpKlineSource>(),
+            100,
+            |mut output| async move {
+                let source: Box<dyn KlineSource> = Box::new(HttpKlineSource::new(
+                    "https://api.binance.com/api/v3/klines?symbol=BTCUSDT&interval=1h&limit=200",
+                    Duration::from_secs(5),
+                ));
+
+                let snapshot = source.snapshot().await;
+                if output.send(Message::SnapshotLoaded(snapshot)).await.is_err() {
+                    return;
+                }
+
+                let mut ticks = source.ticks();
+                while let Some(candle) = ticks.next().await {
+                    if output.send(Message::CandleUpdate(candle)).await.is_err() {
+                        break;
+                    }
+                }
+            },
+        )
+    }
+}
+
+fn main() -> iced::Result {
+    CandleChartApp::run(Settings::default())
 }
 
-fn main() {
-    CandleChartApp::run(Settings::default()).unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn candle_at(time_hour: i64, open: f64, high: f64, low: f64, close: f64, volume: f64) -> Candle {
+        Candle {
+            open,
+            high,
+            low,
+            close,
+            volume: Some(volume),
+            time: Utc.timestamp_opt(0, 0).unwrap() + chrono::Duration::hours(time_hour),
+        }
+    }
+
+    #[test]
+    fn sma_emits_none_until_the_window_fills_then_the_trailing_mean() {
+        let closes = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(
+            sma(&closes, 3),
+            vec![None, None, Some(2.0), Some(3.0), Some(4.0)]
+        );
+    }
+
+    #[test]
+    fn sma_with_a_zero_period_is_all_none() {
+        assert_eq!(sma(&[1.0, 2.0, 3.0], 0), vec![None, None, None]);
+    }
+
+    #[test]
+    fn ema_seeds_with_the_sma_then_follows_the_recurrence() {
+        let closes = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        let values = ema(&closes, 3);
+
+        // period - 1 is the first index with a value, seeded from the SMA
+        assert_eq!(values[..2], [None, None]);
+        assert_eq!(values[2], Some(2.0));
+
+        // k = 2 / (period + 1) = 0.5
+        assert_eq!(values[3], Some(4.0 * 0.5 + 2.0 * 0.5));
+        assert_eq!(values[4], Some(5.0 * 0.5 + 3.0 * 0.5));
+    }
+
+    #[test]
+    fn ema_with_fewer_closes_than_the_period_is_all_none() {
+        assert_eq!(ema(&[1.0, 2.0], 3), vec![None, None]);
+    }
+
+    #[test]
+    fn ema_with_a_zero_period_is_all_none() {
+        assert_eq!(ema(&[1.0, 2.0, 3.0], 0), vec![None, None, None]);
+    }
+
+    #[test]
+    fn visible_series_aggregates_ohlcv_over_a_fixed_bucket() {
+        let candles: Vec<Candle> = (0..10)
+            .map(|i| {
+                let i = i as f64;
+                candle_at(i as i64, i, i + 1.0, i - 0.5, i + 0.5, i * 10.0)
+            })
+            .collect();
+        let chart = CandleChart::new(candles);
+
+        // candle_spacing of 1.5px forces a bucket_size of 2 (2.0 / 1.5 -> ceil -> 2)
+        let candle_spacing = 1.5;
+        let view = ViewRect {
+            first_index: 0.0,
+            visible_count: 10.0,
+            price_center: 0.0,
+            price_span: 1.0,
+        };
+
+        let series = chart.visible_series(view, 0..10, candle_spacing);
+        assert_eq!(series.len(), 5);
+
+        let (_, width, first_bucket) = &series[0];
+        assert_eq!(*width, candle_spacing * 2.0);
+        assert_eq!(first_bucket.open, chart.candles[0].open);
+        assert_eq!(first_bucket.close, chart.candles[1].close);
+        assert_eq!(first_bucket.high, chart.candles[1].high);
+        assert_eq!(first_bucket.low, chart.candles[0].low);
+        assert_eq!(first_bucket.volume, Some(0.0 * 10.0 + 1.0 * 10.0));
+    }
+
+    #[test]
+    fn visible_series_bucket_boundaries_stay_fixed_as_first_index_pans() {
+        let candles: Vec<Candle> = (0..10)
+            .map(|i| {
+                let i = i as f64;
+                candle_at(i as i64, i, i + 1.0, i - 0.5, i + 0.5, i * 10.0)
+            })
+            .collect();
+        let chart = CandleChart::new(candles);
+        let candle_spacing = 1.5;
+
+        let at_rest = ViewRect {
+            first_index: 0.0,
+            visible_count: 10.0,
+            price_center: 0.0,
+            price_span: 1.0,
+        };
+        let panned = ViewRect {
+            first_index: 3.7,
+            ..at_rest
+        };
+
+        let series_at_rest = chart.visible_series(at_rest, 0..10, candle_spacing);
+        let series_panned = chart.visible_series(panned, 0..10, candle_spacing);
+
+        let rest_candles: Vec<&Candle> = series_at_rest.iter().map(|(_, _, c)| c).collect();
+        let panned_candles: Vec<&Candle> = series_panned.iter().map(|(_, _, c)| c).collect();
+
+        // Aggregated OHLCV per bucket is identical regardless of scroll
+        // position; only each bucket's x-center should differ.
+        assert_eq!(
+            rest_candles.iter().map(|c| c.close).collect::<Vec<_>>(),
+            panned_candles.iter().map(|c| c.close).collect::<Vec<_>>()
+        );
+        assert_ne!(series_at_rest[0].0, series_panned[0].0);
+    }
 }